@@ -1,34 +1,332 @@
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use termios;
+use std::time::Duration;
+
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use socket2::{Socket, TcpKeepalive};
+
+use signal_hook::consts::SIGINT;
+
+const UDS_SOCKET_PATH: &str = "/tmp/rusty-race.sock";
+
+/// Per-connection handler threads, collected so `main` can join them
+/// alongside the accept-loop threads before exiting.
+type ConnectionThreads = Arc<Mutex<Vec<thread::JoinHandle<()>>>>;
+
+/// How often accept loops and client read loops wake up to check the shared
+/// shutdown flag.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Generous overall deadline for a TLS handshake to complete, so a slow
+/// client-side key operation or multi-flight TLS 1.2 exchange isn't mistaken
+/// for a dead connection. Individual handshake reads still time out every
+/// `SHUTDOWN_POLL_INTERVAL`.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// First file descriptor handed to us under systemd socket activation.
+const SD_LISTEN_FDS_START: RawFd = 3;
+
+/// A listener inherited from a supervisor (e.g. systemd) via socket activation.
+enum InheritedListener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+/// Reads the `LISTEN_FDS`/`LISTEN_PID`/`LISTEN_FDNAMES` protocol used by systemd
+/// socket activation and, if this process is the intended recipient, reconstructs
+/// the already-bound listeners starting at fd 3. Returns an empty `Vec` if no
+/// activation environment is present.
+fn systemd_activation_listeners() -> Vec<InheritedListener> {
+    let listen_pid = match std::env::var("LISTEN_PID") {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let listen_fds = match std::env::var("LISTEN_FDS") {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    if listen_pid.parse::<u32>() != Ok(process::id()) {
+        return Vec::new();
+    }
+
+    let fd_count: usize = match listen_fds.parse() {
+        Ok(n) => n,
+        Err(_) => return Vec::new(),
+    };
+
+    let fd_names: Vec<String> = std::env::var("LISTEN_FDNAMES")
+        .map(|names| names.split(':').map(str::to_string).collect())
+        .unwrap_or_default();
+
+    (0..fd_count)
+        .map(|i| {
+            let fd = SD_LISTEN_FDS_START + i as RawFd;
+            let is_unix = fd_names
+                .get(i)
+                .is_some_and(|name| name.eq_ignore_ascii_case("unix"));
+
+            if is_unix {
+                InheritedListener::Unix(unsafe { UnixListener::from_raw_fd(fd) })
+            } else {
+                InheritedListener::Tcp(unsafe { TcpListener::from_raw_fd(fd) })
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+enum Origin {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Tcp(addr) => write!(f, "{}", addr),
+            Origin::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// TCP keepalive tuning applied to accepted connections. Any field left `None`
+/// is left at the OS default.
+#[derive(Clone, Copy, Default)]
+struct KeepaliveConfig {
+    time: Option<Duration>,
+    interval: Option<Duration>,
+    retries: Option<u32>,
+}
+
+impl KeepaliveConfig {
+    /// Builds the subset of this config that `socket2::TcpKeepalive` can
+    /// express without the non-default `all` Cargo feature. `retries` is
+    /// applied separately, via a raw `setsockopt(TCP_KEEPCNT)`, by
+    /// `apply_tcp_keepalive` below.
+    fn to_tcp_keepalive(self) -> Option<TcpKeepalive> {
+        if self.time.is_none() && self.interval.is_none() {
+            return None;
+        }
+
+        let mut ka = TcpKeepalive::new();
+        if let Some(time) = self.time {
+            ka = ka.with_time(time);
+        }
+        if let Some(interval) = self.interval {
+            ka = ka.with_interval(interval);
+        }
+        Some(ka)
+    }
+}
+
+fn default_keepalive_config() -> KeepaliveConfig {
+    KeepaliveConfig {
+        time: Some(Duration::from_secs(30)),
+        interval: Some(Duration::from_secs(10)),
+        retries: Some(3),
+    }
+}
+
+/// Applies `config` to `stream` via socket2 (plus a raw `setsockopt` for
+/// `retries`, which socket2 only exposes behind its `all` feature),
+/// returning the (possibly unmodified) stream. A config with every field
+/// `None` is a no-op.
+fn apply_tcp_keepalive(stream: TcpStream, config: &KeepaliveConfig) -> TcpStream {
+    let stream = match config.to_tcp_keepalive() {
+        Some(ka) => {
+            let socket = Socket::from(stream);
+            if let Err(e) = socket.set_tcp_keepalive(&ka) {
+                eprintln!("Error setting TCP keepalive: {}", e);
+            }
+            TcpStream::from(socket)
+        }
+        None => stream,
+    };
+
+    if let Some(retries) = config.retries {
+        set_tcp_keepcnt(&stream, retries);
+    }
+
+    stream
+}
+
+/// Sets `TCP_KEEPCNT` directly via `setsockopt`, since `socket2::TcpKeepalive::with_retries`
+/// is gated behind the `all` feature and this crate only depends on the default feature set.
+fn set_tcp_keepcnt(stream: &TcpStream, retries: u32) {
+    let value: libc::c_int = retries as libc::c_int;
+    let ret = unsafe {
+        libc::setsockopt(
+            stream.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_KEEPCNT,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret != 0 {
+        eprintln!(
+            "Error setting TCP_KEEPCNT: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+/// A port to listen on, optionally terminating TLS before bytes reach the
+/// capture/echo pipeline. Plaintext and TLS ports can be mixed freely.
+struct PortConfig {
+    port: u16,
+    tls: Option<Arc<ServerConfig>>,
+}
+
+/// Loads a PEM certificate chain and private key from disk and builds a
+/// `rustls::ServerConfig` that can be shared across every TLS-enabled port.
+fn load_tls_config(cert_path: &Path, key_path: &Path) -> std::io::Result<Arc<ServerConfig>> {
+    let mut cert_reader = BufReader::new(File::open(cert_path)?);
+    let certs = rustls_pemfile::certs(&mut cert_reader).collect::<std::io::Result<Vec<_>>>()?;
+
+    let mut key_reader = BufReader::new(File::open(key_path)?);
+    let key = rustls_pemfile::private_key(&mut key_reader)?.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("no private key found in {}", key_path.display()),
+        )
+    })?;
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    Ok(Arc::new(config))
+}
+
+/// Summarizes the negotiated protocol/ALPN/SNI of a completed TLS handshake
+/// for display in the connection's `extra_message`.
+fn describe_tls_connection(conn: &ServerConnection) -> String {
+    let protocol = conn
+        .protocol_version()
+        .map(|v| format!("{:?}", v))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut parts = vec![format!("TLS {}", protocol)];
+
+    if let Some(alpn) = conn.alpn_protocol() {
+        parts.push(format!("alpn={}", String::from_utf8_lossy(alpn)));
+    }
+    if let Some(sni) = conn.server_name() {
+        parts.push(format!("sni={}", sni));
+    }
+
+    parts.join(", ")
+}
 
 struct IncomingData {
-    peer_addr: std::net::SocketAddr,
+    origin: Origin,
     port: u16,
     data: Vec<u8>,
     timestamp: std::time::Instant,
     extra_message: Option<String>,
 }
 
-fn handle_client(
+/// How captured bytes are rendered to the console.
+#[derive(Clone, Copy)]
+enum DisplayMode {
+    /// The original `{:?}` byte-slice dump.
+    Raw,
+    /// A canonical 16-bytes-per-row hexdump with an ASCII gutter.
+    Hex,
+    /// Both of the above, raw first.
+    Both,
+}
+
+impl DisplayMode {
+    fn from_env() -> Self {
+        match std::env::var("RUSTY_RACE_DISPLAY_MODE") {
+            Ok(v) if v.eq_ignore_ascii_case("raw") => DisplayMode::Raw,
+            Ok(v) if v.eq_ignore_ascii_case("both") => DisplayMode::Both,
+            _ => DisplayMode::Hex,
+        }
+    }
+}
+
+/// Renders `data` as a canonical hexdump: an 8-digit hex offset, 16
+/// space-separated uppercase hex bytes per row (grouped 8+8), and an ASCII
+/// gutter where non-printable bytes render as `.`.
+fn format_hexdump(data: &[u8]) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+
+    for (row, chunk) in data.chunks(16).enumerate() {
+        let _ = write!(out, "{:08X}  ", row * 16);
+
+        for (i, byte) in chunk.iter().enumerate() {
+            let _ = write!(out, "{:02X} ", byte);
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        for i in chunk.len()..16 {
+            out.push_str("   ");
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+
+        out.push(' ');
+        for byte in chunk {
+            let c = *byte as char;
+            out.push(if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '.'
+            });
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn print_received_data(data: &[u8], mode: DisplayMode) {
+    match mode {
+        DisplayMode::Raw => println!("{:?}", data),
+        DisplayMode::Hex => print!("{}", format_hexdump(data)),
+        DisplayMode::Both => {
+            println!("{:?}", data);
+            print!("{}", format_hexdump(data));
+        }
+    }
+}
+
+fn handle_client<S: Read + Write>(
     port: u16,
-    mut stream: TcpStream,
+    mut stream: S,
+    origin: Origin,
+    shutdown: Arc<AtomicBool>,
     tx: std::sync::mpsc::Sender<IncomingData>,
 ) -> std::io::Result<()> {
     let message: Option<String> = Some("Connection received".to_string());
 
     let mut connection = IncomingData {
-        peer_addr: stream.peer_addr().unwrap(),
+        origin: origin.clone(),
         port,
         data: Vec::new(),
         timestamp: std::time::Instant::now(),
         extra_message: message,
     };
 
-    let peer_addr = stream.peer_addr().unwrap();
-
     let mut buf = [0; 1024];
     loop {
         let bytes_read = stream.read(&mut buf);
@@ -39,18 +337,38 @@ fn handle_client(
                 }
 
                 let bytes = IncomingData {
-                    peer_addr: peer_addr,
-                    port: port,
-                    data: buf.to_vec(),
+                    origin: origin.clone(),
+                    port,
+                    data: buf[..bytes_read].to_vec(),
                     timestamp: std::time::Instant::now(),
                     extra_message: None,
                 };
 
-                stream.write(&buf[..bytes_read]).unwrap();
-                // Send the bytes read as a string of 2-digit uppercase hex values
+                stream.write_all(&buf[..bytes_read]).unwrap();
                 tx.send(bytes).unwrap();
             }
             Err(e) => {
+                // The read timeout set by the caller expiring just means no
+                // data arrived in this window; wake up, check for shutdown,
+                // and keep waiting otherwise.
+                if e.kind() == std::io::ErrorKind::WouldBlock {
+                    if shutdown.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    continue;
+                }
+                // A keepalive probe that exhausted its retries surfaces as a
+                // timeout; report it instead of hanging the thread forever.
+                if e.kind() == std::io::ErrorKind::TimedOut {
+                    let _ = tx.send(IncomingData {
+                        origin: origin.clone(),
+                        port,
+                        data: Vec::new(),
+                        timestamp: std::time::Instant::now(),
+                        extra_message: Some("Connection timed out".to_string()),
+                    });
+                    break;
+                }
                 //  If connectionion reset by peer, just break the loop
                 if e.kind() == std::io::ErrorKind::ConnectionReset {
                     break;
@@ -77,13 +395,51 @@ fn handle_client(
     Ok(())
 }
 
-fn start_tcp_server(port: u16, tx: std::sync::mpsc::Sender<IncomingData>) {
-    let listener = TcpListener::bind(format!("0.0.0.0:{}", port)).unwrap();
+fn start_tcp_server(
+    config: PortConfig,
+    keepalive: KeepaliveConfig,
+    shutdown: Arc<AtomicBool>,
+    connection_threads: ConnectionThreads,
+    tx: std::sync::mpsc::Sender<IncomingData>,
+) {
+    let listener = TcpListener::bind(format!("0.0.0.0:{}", config.port)).unwrap();
+
+    run_tcp_listener(
+        listener,
+        config.tls,
+        keepalive,
+        shutdown,
+        connection_threads,
+        tx,
+    );
+}
 
+fn run_tcp_listener(
+    listener: TcpListener,
+    tls: Option<Arc<ServerConfig>>,
+    keepalive: KeepaliveConfig,
+    shutdown: Arc<AtomicBool>,
+    connection_threads: ConnectionThreads,
+    tx: std::sync::mpsc::Sender<IncomingData>,
+) {
     let local_port = listener.local_addr().unwrap().port();
+    listener.set_nonblocking(true).unwrap();
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Error accepting TCP connection: {}", e);
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                continue;
+            }
+        };
 
-    for stream in listener.incoming() {
-        let stream = stream.unwrap();
+        let stream = apply_tcp_keepalive(stream, &keepalive);
 
         let s = match stream.peer_addr() {
             Ok(s) => s,
@@ -91,58 +447,258 @@ fn start_tcp_server(port: u16, tx: std::sync::mpsc::Sender<IncomingData>) {
         };
 
         let handle_tx = tx.clone();
+        let handle_shutdown = shutdown.clone();
+        let origin = Origin::Tcp(s);
+
+        match tls.clone() {
+            Some(tls_config) => {
+                let handle = thread::spawn(move || {
+                    let conn = match ServerConnection::new(tls_config) {
+                        Ok(conn) => conn,
+                        Err(e) => {
+                            eprintln!("TLS handshake setup failed: {}", e);
+                            return;
+                        }
+                    };
+
+                    // Drive the handshake in short slices against the same
+                    // poll interval used everywhere else, checking shutdown
+                    // between each one, so a stalled client can't make
+                    // shutdown block for the full TLS_HANDSHAKE_TIMEOUT --
+                    // only an actually-slow-but-progressing handshake does.
+                    let _ = stream.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL));
+                    let mut tls_stream = StreamOwned::new(conn, stream);
+
+                    let handshake_deadline = std::time::Instant::now() + TLS_HANDSHAKE_TIMEOUT;
+                    let handshake_result = loop {
+                        match tls_stream.conn.complete_io(&mut tls_stream.sock) {
+                            Ok(_) => break Ok(()),
+                            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                                if handle_shutdown.load(Ordering::Relaxed) {
+                                    break Err(std::io::Error::new(
+                                        std::io::ErrorKind::Interrupted,
+                                        "shutdown requested",
+                                    ));
+                                }
+                                if std::time::Instant::now() >= handshake_deadline {
+                                    break Err(std::io::Error::new(
+                                        std::io::ErrorKind::TimedOut,
+                                        "TLS handshake timed out",
+                                    ));
+                                }
+                                continue;
+                            }
+                            Err(e) => break Err(e),
+                        }
+                    };
+
+                    if let Err(e) = handshake_result {
+                        eprintln!("TLS handshake failed: {}", e);
+                        return;
+                    }
+
+                    if let Err(e) = handle_tx.send(IncomingData {
+                        origin: origin.clone(),
+                        port: local_port,
+                        data: Vec::new(),
+                        timestamp: std::time::Instant::now(),
+                        extra_message: Some(format!(
+                            "Connection established ({})",
+                            describe_tls_connection(&tls_stream.conn)
+                        )),
+                    }) {
+                        eprintln!("Error sending connection established message: {}", e);
+                    }
+
+                    handle_client(local_port, tls_stream, origin, handle_shutdown, handle_tx)
+                        .unwrap();
+                });
+                connection_threads.lock().unwrap().push(handle);
+            }
+            None => {
+                let _ = stream.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL));
+
+                let handle = thread::spawn(move || {
+                    handle_client(local_port, stream, origin, handle_shutdown, handle_tx).unwrap();
+                });
+                connection_threads.lock().unwrap().push(handle);
+
+                if let Err(e) = tx.send(IncomingData {
+                    origin: Origin::Tcp(s),
+                    port: local_port,
+                    data: Vec::new(),
+                    timestamp: std::time::Instant::now(),
+                    extra_message: Some("Connection established".to_string()),
+                }) {
+                    eprintln!("Error sending connection established message: {}", e);
+                }
+            }
+        }
+    }
+}
+
+fn start_uds_server(
+    path: &Path,
+    shutdown: Arc<AtomicBool>,
+    connection_threads: ConnectionThreads,
+    tx: std::sync::mpsc::Sender<IncomingData>,
+) {
+    let _ = std::fs::remove_file(path);
+
+    let listener = UnixListener::bind(path).unwrap();
+
+    run_uds_listener(
+        listener,
+        Some(path.to_path_buf()),
+        shutdown,
+        connection_threads,
+        tx,
+    );
+
+    let _ = std::fs::remove_file(path);
+}
+
+fn run_uds_listener(
+    listener: UnixListener,
+    path: Option<PathBuf>,
+    shutdown: Arc<AtomicBool>,
+    connection_threads: ConnectionThreads,
+    tx: std::sync::mpsc::Sender<IncomingData>,
+) {
+    let path = path.unwrap_or_else(|| {
+        listener
+            .local_addr()
+            .ok()
+            .and_then(|addr| addr.as_pathname().map(Path::to_path_buf))
+            .unwrap_or_else(|| PathBuf::from("<unix>"))
+    });
+
+    listener.set_nonblocking(true).unwrap();
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let stream: UnixStream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Error accepting Unix socket connection: {}", e);
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        let _ = stream.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL));
+
+        let origin = Origin::Unix(path.clone());
+
+        let handle_tx = tx.clone();
+        let handle_origin = origin.clone();
+        let handle_shutdown = shutdown.clone();
 
-        thread::spawn(move || {
-            handle_client(local_port, stream, handle_tx).unwrap();
+        let handle = thread::spawn(move || {
+            handle_client(0, stream, handle_origin, handle_shutdown, handle_tx).unwrap();
         });
+        connection_threads.lock().unwrap().push(handle);
 
         if let Err(e) = tx.send(IncomingData {
-            peer_addr: s,
-            port: local_port,
+            origin,
+            port: 0,
             data: Vec::new(),
             timestamp: std::time::Instant::now(),
             extra_message: Some("Connection established".to_string()),
         }) {
             eprintln!("Error sending connection established message: {}", e);
         }
-
-        // Close the listener after the first connection and end the server
-        break;
     }
 }
 
-fn watch_for_keypress(tx: std::sync::mpsc::Sender<IncomingData>) {
+/// Watches stdin for the 'q' keypress and, concurrently, `sigint_read` — the
+/// read end of the self-pipe a SIGINT handler writes to in `main`. Both fds
+/// are polled together so an incoming SIGINT unblocks this thread the same
+/// way 'q' does, instead of being silently swallowed by a blocking stdin
+/// read (termios leaves ISIG set, so Ctrl-C still raises SIGINT, and the
+/// handler registered in `main` replaces the OS default action; std also
+/// retries reads across EINTR, so without this self-pipe a signal-only
+/// handler would never be observed here at all).
+fn watch_for_keypress(
+    shutdown: Arc<AtomicBool>,
+    sigint_read: RawFd,
+    tx: std::sync::mpsc::Sender<IncomingData>,
+) {
     // Use raw mode to avoid waiting for a newline
     let existing_termios = termios::Termios::from_fd(0).unwrap();
-    let mut termios_in_noncanonical_mode = existing_termios.clone();
+    let mut termios_in_noncanonical_mode = existing_termios;
     termios_in_noncanonical_mode.c_lflag &= !(termios::ICANON | termios::ECHO);
 
     let _ = termios::tcsetattr(0, termios::TCSANOW, &termios_in_noncanonical_mode);
 
+    let mut fds = [
+        libc::pollfd {
+            fd: 0,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        libc::pollfd {
+            fd: sigint_read,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+
     loop {
-        let mut buf = [0; 1];
-        let _ = std::io::stdin().read(&mut buf);
-
-        let key = buf[0] as char;
-
-        if key == 'q' {
-            tx.send(IncomingData {
-                peer_addr: "0.0.0.0:0".parse().unwrap(),
-                port: 0,
-                data: Vec::new(),
-                timestamp: std::time::Instant::now(),
-                extra_message: Some("Shutdown".to_string()),
-            })
-            .unwrap();
+        fds[0].revents = 0;
+        fds[1].revents = 0;
+
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+        if ready < 0 {
+            continue;
+        }
+
+        if fds[1].revents & libc::POLLIN != 0 {
+            let mut discard = [0u8; 16];
+            let _ = unsafe {
+                libc::read(
+                    sigint_read,
+                    discard.as_mut_ptr() as *mut libc::c_void,
+                    discard.len(),
+                )
+            };
+            shutdown.store(true, Ordering::Relaxed);
             break;
         }
-        if key == '?' {
-            println!("Press 'q' to quit");
+
+        if fds[0].revents & libc::POLLIN != 0 {
+            let mut buf = [0; 1];
+            let _ = std::io::stdin().read(&mut buf);
+
+            let key = buf[0] as char;
+
+            if key == 'q' {
+                shutdown.store(true, Ordering::Relaxed);
+                break;
+            }
+            if key == '?' {
+                println!("Press 'q' to quit");
+            }
+        }
+
+        if shutdown.load(Ordering::Relaxed) {
+            break;
         }
     }
 
     // Reset the terminal to normal mode
     let _ = termios::tcsetattr(0, termios::TCSANOW, &existing_termios);
+
+    let _ = tx.send(IncomingData {
+        origin: Origin::Tcp("0.0.0.0:0".parse().unwrap()),
+        port: 0,
+        data: Vec::new(),
+        timestamp: std::time::Instant::now(),
+        extra_message: Some("Shutdown".to_string()),
+    });
 }
 
 fn set_panic_hook() {
@@ -150,7 +706,7 @@ fn set_panic_hook() {
     std::panic::set_hook(Box::new(move |info| {
         // Reset the terminal to normal mode
         let existing_termios = termios::Termios::from_fd(0).unwrap();
-        let mut termios_in_cononical_mode = existing_termios.clone();
+        let mut termios_in_cononical_mode = existing_termios;
         termios_in_cononical_mode.c_lflag |= termios::ICANON | termios::ECHO;
         let _ = termios::tcsetattr(0, termios::TCSANOW, &termios_in_cononical_mode);
 
@@ -164,24 +720,135 @@ fn main() -> std::io::Result<()> {
 
     println!("Hello, world!");
 
-    let listening_ports = vec![3000, 3001, 3002];
+    let display_mode = DisplayMode::from_env();
+
+    // Opt-in TLS: set RUSTY_RACE_TLS_CERT/RUSTY_RACE_TLS_KEY to terminate TLS
+    // on the last listening port while leaving the others plaintext.
+    let tls_config = match (
+        std::env::var("RUSTY_RACE_TLS_CERT"),
+        std::env::var("RUSTY_RACE_TLS_KEY"),
+    ) {
+        (Ok(cert), Ok(key)) => match load_tls_config(Path::new(&cert), Path::new(&key)) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("Failed to load TLS config: {}", e);
+                None
+            }
+        },
+        _ => None,
+    };
+
+    let tls_config_present = tls_config.is_some();
+
+    let listening_ports = vec![
+        PortConfig {
+            port: 3000,
+            tls: None,
+        },
+        PortConfig {
+            port: 3001,
+            tls: None,
+        },
+        PortConfig {
+            port: 3002,
+            tls: tls_config,
+        },
+    ];
 
     let (tx, rx) = channel::<IncomingData>();
 
-    for port in listening_ports {
-        let tx = tx.clone();
-        thread::spawn(move || {
-            start_tcp_server(port, tx);
-        });
+    let keepalive = default_keepalive_config();
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let connection_threads: ConnectionThreads = Arc::new(Mutex::new(Vec::new()));
+
+    let mut server_threads = Vec::new();
+
+    let inherited_listeners = systemd_activation_listeners();
+
+    if !inherited_listeners.is_empty() {
+        println!(
+            "Using {} socket-activated listener(s) from systemd",
+            inherited_listeners.len()
+        );
+
+        // Inherited listeners are reconstructed from raw fds with no per-fd
+        // way (yet) to say "this one should terminate TLS", so socket
+        // activation always serves plaintext. Warn rather than silently
+        // dropping RUSTY_RACE_TLS_CERT/RUSTY_RACE_TLS_KEY on the floor.
+        if tls_config_present {
+            eprintln!(
+                "Warning: RUSTY_RACE_TLS_CERT/RUSTY_RACE_TLS_KEY are set but TLS is not \
+                 supported for socket-activated listeners; they will serve plaintext"
+            );
+        }
+
+        for listener in inherited_listeners {
+            let tx = tx.clone();
+            let shutdown = shutdown.clone();
+            let connection_threads = connection_threads.clone();
+            match listener {
+                InheritedListener::Tcp(listener) => {
+                    server_threads.push(thread::spawn(move || {
+                        run_tcp_listener(
+                            listener,
+                            None,
+                            keepalive,
+                            shutdown,
+                            connection_threads,
+                            tx,
+                        );
+                    }));
+                }
+                InheritedListener::Unix(listener) => {
+                    server_threads.push(thread::spawn(move || {
+                        run_uds_listener(listener, None, shutdown, connection_threads, tx);
+                    }));
+                }
+            }
+        }
+    } else {
+        for port in listening_ports {
+            let tx = tx.clone();
+            let shutdown = shutdown.clone();
+            let connection_threads = connection_threads.clone();
+            server_threads.push(thread::spawn(move || {
+                start_tcp_server(port, keepalive, shutdown, connection_threads, tx);
+            }));
+        }
+
+        let uds_tx = tx.clone();
+        let uds_shutdown = shutdown.clone();
+        let uds_connection_threads = connection_threads.clone();
+        server_threads.push(thread::spawn(move || {
+            start_uds_server(
+                Path::new(UDS_SOCKET_PATH),
+                uds_shutdown,
+                uds_connection_threads,
+                uds_tx,
+            );
+        }));
     }
 
+    // Self-pipe so a SIGINT can unblock watch_for_keypress's blocking wait on
+    // stdin: std retries reads across EINTR, so a signal-only flag would
+    // never actually be observed until the next keypress.
+    let mut sigint_fds = [0; 2];
+    if unsafe { libc::pipe(sigint_fds.as_mut_ptr()) } != 0 {
+        panic!("failed to create self-pipe for SIGINT handling");
+    }
+    let (sigint_read, sigint_write) = (sigint_fds[0], sigint_fds[1]);
+
+    signal_hook::low_level::pipe::register(SIGINT, sigint_write)
+        .expect("failed to register SIGINT handler");
+
     let keypress_tx = tx.clone();
+    let keypress_shutdown = shutdown.clone();
 
     let keypress_builder =
         thread::Builder::new()
             .name("keypress".to_string())
             .spawn(move || {
-                watch_for_keypress(keypress_tx);
+                watch_for_keypress(keypress_shutdown, sigint_read, keypress_tx);
             })?;
 
     for received in rx {
@@ -195,17 +862,27 @@ fn main() -> std::io::Result<()> {
             }
             None => {
                 println!(
-                    "{}: Received {} bytes from {}:{}",
+                    "{}: Received {} bytes from {} (port {})",
                     received.timestamp.elapsed().as_secs(),
                     received.data.len(),
-                    received.peer_addr.ip(),
+                    received.origin,
                     received.port
                 );
-                println!("Received from port {}: {:?}", received.port, received.data);
+                print_received_data(&received.data, display_mode);
             }
         }
     }
 
+    shutdown.store(true, Ordering::Relaxed);
+
+    for handle in server_threads {
+        let _ = handle.join();
+    }
+
+    for handle in connection_threads.lock().unwrap().drain(..) {
+        let _ = handle.join();
+    }
+
     keypress_builder.join().unwrap();
 
     println!("Goodbye, world!");